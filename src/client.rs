@@ -0,0 +1,363 @@
+//! Transport layer on top of [`parse`]: send an `msh` command, collect the
+//! reply lines up to the next prompt, and hand back a typed [`ParsedMessage`]
+//! instead of raw bytes.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::parser::{self, normalize_line, Free, ParsedMessage, ProcessParams, PROMPT};
+
+/// Blocking send-and-parse over a transport owned by the caller.
+pub trait SyncClient {
+    /// Write `cmd`, read until the device re-prints its prompt, and parse
+    /// the collected lines as a reply to `cmd`.
+    fn send_and_parse(&mut self, cmd: &str) -> Result<ParsedMessage>;
+}
+
+/// Async equivalent of [`SyncClient`]. Unlike `SyncClient::send_and_parse`,
+/// which blocks for the reply (re-issuing the command a bounded number of
+/// times when the reply is empty or unrecognized),
+/// `send` fires the request without waiting for a reply at all; pair it
+/// with a `StreamParser` fed from the same transport's read side to get the
+/// matching `ParsedMessage` back later.
+#[async_trait::async_trait]
+pub trait AsyncClient {
+    async fn send(&mut self, cmd: &str) -> Result<()>;
+}
+
+/// Default [`AsyncClient`] over any `tokio::io::AsyncWrite` transport (e.g.
+/// a `tokio_serial::SerialStream` handle). Unlike [`MshClient`] it owns no
+/// read side at all: `send` only writes the command, and the caller is
+/// expected to be feeding the transport's read half into a [`StreamParser`]
+/// (see `crate::stream`) to pick up the matching reply once it arrives.
+pub struct AsyncMshClient<T> {
+    io: T,
+}
+
+impl<T: tokio::io::AsyncWrite + Unpin + Send> AsyncMshClient<T> {
+    pub fn new(io: T) -> Self {
+        Self { io }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: tokio::io::AsyncWrite + Unpin + Send> AsyncClient for AsyncMshClient<T> {
+    async fn send(&mut self, cmd: &str) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+        self.io.write_all(cmd.as_bytes()).await?;
+        self.io.write_all(b"\n").await?;
+        self.io.flush().await?;
+        Ok(())
+    }
+}
+
+/// High-level, typed reads built on top of [`SyncClient::send_and_parse`].
+/// Hides the request string, the read loop, and the retry-on-garbled-reply
+/// behind one call per command, so callers get a typed result instead of
+/// matching on `ParsedMessage` themselves.
+pub trait Client: SyncClient {
+    fn read_free(&mut self) -> Result<Free> {
+        match self.send_and_parse("free")? {
+            ParsedMessage::Free(f) => Ok(f),
+            other => Err(anyhow!("unexpected reply to `free`: {other:?}")),
+        }
+    }
+
+    fn read_cur_pro(&mut self) -> Result<ProcessParams> {
+        match self.send_and_parse("cur_pro")? {
+            ParsedMessage::ProcessCur(p) => Ok(p),
+            other => Err(anyhow!("unexpected reply to `cur_pro`: {other:?}")),
+        }
+    }
+
+    fn read_feeder_pro(&mut self) -> Result<ProcessParams> {
+        match self.send_and_parse("feeder_pro")? {
+            ParsedMessage::ProcessFeeder(p) => Ok(p),
+            other => Err(anyhow!("unexpected reply to `feeder_pro`: {other:?}")),
+        }
+    }
+}
+
+impl<T: SyncClient> Client for T {}
+
+/// Transports that can bound how long a blocking read may wait. Required by
+/// [`MshClient`] so `read_timeout` is enforced by the transport itself (e.g.
+/// `TTYPort::set_timeout`, `TcpStream::set_read_timeout`) rather than only
+/// checked between completed `read_line` calls, which can't interrupt a read
+/// that's already blocked.
+pub trait SetReadTimeout {
+    fn set_read_timeout(&mut self, timeout: Duration) -> std::io::Result<()>;
+}
+
+/// Default [`SyncClient`] over any `Read + Write` transport (e.g. a
+/// `serialport::TTYPort` handle) that can also bound its own read timeout.
+pub struct MshClient<T> {
+    io: T,
+    read_timeout: Duration,
+    retries: u32,
+}
+
+impl<T: Read + Write + SetReadTimeout> MshClient<T> {
+    pub fn new(mut io: T) -> Result<Self> {
+        let read_timeout = Duration::from_millis(500);
+        io.set_read_timeout(read_timeout)?;
+        Ok(Self {
+            io,
+            read_timeout,
+            retries: 2,
+        })
+    }
+
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.io.set_read_timeout(timeout)?;
+        self.read_timeout = timeout;
+        Ok(self)
+    }
+
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    fn write_command(&mut self, cmd: &str) -> Result<()> {
+        self.io.write_all(cmd.as_bytes())?;
+        self.io.write_all(b"\n")?;
+        self.io.flush()?;
+        Ok(())
+    }
+
+    /// Read lines until the `msh >` prompt reappears, dropping the echoed
+    /// command line, or until `read_timeout` elapses with no prompt seen.
+    /// Each individual read is itself bounded by `read_timeout` via
+    /// [`SetReadTimeout`], so a stalled device can't block this loop forever;
+    /// `deadline` is a backstop against many small reads each returning just
+    /// under the per-read timeout. EOF (the transport closing) fails
+    /// immediately rather than spinning until the deadline.
+    fn read_reply(&mut self, cmd: &str) -> Result<Vec<String>> {
+        let deadline = Instant::now() + self.read_timeout;
+        let mut reader = ByteLineReader::new(&mut self.io);
+        let mut lines = Vec::new();
+        loop {
+            if Instant::now() > deadline {
+                return Err(anyhow!("timed out waiting for `{cmd}` reply"));
+            }
+            let line = match reader.read_line()? {
+                Some(l) => l,
+                None => {
+                    return Err(anyhow!(
+                        "transport closed while waiting for `{cmd}` reply"
+                    ))
+                }
+            };
+            let line = normalize_line(&line);
+            if line == PROMPT {
+                break;
+            }
+            if line == cmd || line.is_empty() {
+                // echoed command line or blank noise
+                continue;
+            }
+            lines.push(line);
+        }
+        Ok(lines)
+    }
+}
+
+impl<T: Read + Write + SetReadTimeout> SyncClient for MshClient<T> {
+    fn send_and_parse(&mut self, cmd: &str) -> Result<ParsedMessage> {
+        let mut attempts = 0;
+        loop {
+            self.write_command(cmd)?;
+            let lines = self.read_reply(cmd)?;
+            let msg = parser::parse(cmd, &lines);
+            let retryable = lines.is_empty() || matches!(msg, ParsedMessage::Unknown { .. });
+            if !retryable || attempts >= self.retries {
+                return Ok(msg);
+            }
+            attempts += 1;
+        }
+    }
+}
+
+/// Minimal line reader over a blocking `Read` that doesn't require the
+/// underlying stream to support `BufRead` itself.
+struct ByteLineReader<'a, T> {
+    reader: BufReader<&'a mut T>,
+}
+
+impl<'a, T: Read> ByteLineReader<'a, T> {
+    fn new(io: &'a mut T) -> Self {
+        Self {
+            reader: BufReader::new(io),
+        }
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>> {
+        let mut buf = String::new();
+        let n = self.reader.read_line(&mut buf)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        Ok(Some(buf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// In-memory transport double: reads come from a fixed byte buffer,
+    /// writes are discarded, and `set_read_timeout` just records its
+    /// argument so tests can assert `MshClient` asked for one.
+    struct MockTransport {
+        input: Cursor<Vec<u8>>,
+        last_timeout: Option<Duration>,
+    }
+
+    impl MockTransport {
+        fn new(input: &[u8]) -> Self {
+            Self {
+                input: Cursor::new(input.to_vec()),
+                last_timeout: None,
+            }
+        }
+    }
+
+    impl Read for MockTransport {
+        // Never hands back more than one line per call, mirroring a real
+        // serial port (which only has what the device has sent so far
+        // buffered, not the whole future conversation). `read_reply` builds
+        // a fresh `BufReader` per call and relies on never over-reading past
+        // what it has consumed; with a `Read` that returns everything at
+        // once, the first `BufReader` would swallow the retry's reply and the
+        // retry test would fail for a reason a real port doesn't have.
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let pos = self.input.position() as usize;
+            let data = self.input.get_ref();
+            let end = match data[pos..].iter().position(|&b| b == b'\n') {
+                Some(i) => pos + i + 1,
+                None => data.len(),
+            };
+            let n = (end - pos).min(buf.len());
+            let n = self.input.read(&mut buf[..n])?;
+            Ok(n)
+        }
+    }
+
+    impl Write for MockTransport {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SetReadTimeout for MockTransport {
+        fn set_read_timeout(&mut self, timeout: Duration) -> std::io::Result<()> {
+            self.last_timeout = Some(timeout);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_new_sets_read_timeout_on_transport() {
+        let client = MshClient::new(MockTransport::new(b"")).unwrap();
+        assert_eq!(client.io.last_timeout, Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_with_read_timeout_updates_transport_timeout() {
+        let client = MshClient::new(MockTransport::new(b""))
+            .unwrap()
+            .with_read_timeout(Duration::from_millis(50))
+            .unwrap();
+        assert_eq!(client.io.last_timeout, Some(Duration::from_millis(50)));
+        assert_eq!(client.read_timeout, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_send_and_parse_retries_on_empty_reply_then_succeeds() {
+        // First reply is just the echoed command and an immediate prompt
+        // (no reply lines at all), which is retryable; second reply has the
+        // actual `free` fields.
+        let input =
+            b"free\r\nmsh >\r\nfree\r\ntotal   : 10\r\nused    : 5\r\nmaximum : 10\r\nmsh >\r\n";
+        let mut client = MshClient::new(MockTransport::new(input)).unwrap();
+        match client.send_and_parse("free").unwrap() {
+            ParsedMessage::Free(f) => assert_eq!(f.total, 10),
+            other => panic!("unexpected reply: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_send_and_parse_fails_fast_on_eof_instead_of_retrying() {
+        // Device echoes the command but the connection then closes before
+        // any prompt or reply line shows up.
+        let input = b"free\r\n";
+        let mut client = MshClient::new(MockTransport::new(input)).unwrap();
+        let err = client.send_and_parse("free").unwrap_err();
+        assert!(err.to_string().contains("transport closed"));
+    }
+
+    #[test]
+    fn test_read_reply_times_out_when_prompt_never_arrives() {
+        // A zero-length timeout means the deadline has already passed by the
+        // time `read_reply`'s first check runs, regardless of how fast the
+        // mock transport itself can produce bytes.
+        let input = b"free\r\nstill waiting\r\n";
+        let mut client = MshClient::new(MockTransport::new(input))
+            .unwrap()
+            .with_read_timeout(Duration::from_millis(0))
+            .unwrap();
+        let err = client.send_and_parse("free").unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    /// In-memory `AsyncWrite` transport double: writes accumulate into a
+    /// buffer so tests can assert exactly what `AsyncMshClient::send` wrote.
+    struct MockAsyncTransport {
+        written: Vec<u8>,
+    }
+
+    impl MockAsyncTransport {
+        fn new() -> Self {
+            Self { written: Vec::new() }
+        }
+    }
+
+    impl tokio::io::AsyncWrite for MockAsyncTransport {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            self.written.extend_from_slice(buf);
+            std::task::Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_client_send_writes_command_and_newline() {
+        let mut client = AsyncMshClient::new(MockAsyncTransport::new());
+        client.send("free").await.unwrap();
+        assert_eq!(client.io.written, b"free\n");
+    }
+}
@@ -0,0 +1,333 @@
+//! Diagnostics-collecting parse mode.
+//!
+//! `parse` swallows anything it doesn't recognize into `extras` and quietly
+//! coerces bad values to `None`, which is the right default for callers who
+//! just want a best-effort `ParsedMessage`. `parse_verbose` is for the
+//! opposite case: detecting firmware/protocol drift by surfacing exactly
+//! which line and key misbehaved. It leaves the lenient `parse` untouched.
+
+use std::collections::HashSet;
+
+use crate::parser::{self, normalize_line, strip_prompt_noise, ParsedMessage, ProcessParams};
+
+/// One thing that looked wrong while parsing a reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Index into the normalized, prompt-stripped line list.
+    pub line: usize,
+    /// The offending key, when the reason is key/value-shaped.
+    pub key: Option<String>,
+    /// The offending value, when the reason is key/value-shaped.
+    pub value: Option<String>,
+    pub reason: DiagnosticReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticReason {
+    /// A key this parser doesn't know how to map onto a typed field.
+    UnknownKey,
+    /// A value that should have been an integer but didn't parse as one.
+    IntegerParseFailure,
+    /// The same field was set more than once in the same reply.
+    DuplicateField,
+    /// A positional `len` with no preceding `out_speed`/`in_speed` to
+    /// attach it to.
+    DanglingLen,
+}
+
+/// Parse `lines` as a reply to `cmd`, same as [`parser::parse`], but also
+/// return a list of [`Diagnostic`]s describing anything that looked like
+/// protocol drift. Diagnostics are currently collected in detail for
+/// `cur_pro`/`feeder_pro`, where the device's line shapes are the least
+/// regular; other commands return the same message as `parse` with no
+/// diagnostics.
+pub fn parse_verbose(cmd: &str, lines: &[String]) -> (ParsedMessage, Vec<Diagnostic>) {
+    let mut norm: Vec<String> = lines
+        .iter()
+        .map(|s| normalize_line(s))
+        .filter(|s| !s.is_empty())
+        .collect();
+    strip_prompt_noise(&mut norm);
+
+    match cmd {
+        "cur_pro" => {
+            let (p, diags) = parse_process_like_verbose(&norm);
+            (ParsedMessage::ProcessCur(p), diags)
+        }
+        "feeder_pro" => {
+            let (p, diags) = parse_process_like_verbose(&norm);
+            (ParsedMessage::ProcessFeeder(p), diags)
+        }
+        _ => (parser::parse(cmd, lines), Vec::new()),
+    }
+}
+
+/// Known device keys for `cur_pro`/`feeder_pro` lines, used so that a known
+/// key seen outside the line shape it belongs to is not reported as
+/// `UnknownKey` (no diagnostic is emitted for it).
+fn known_field(key: &str) -> bool {
+    matches!(
+        key,
+        "power"
+            | "fre"
+            | "duty"
+            | "mode"
+            | "head_mode"
+            | "width"
+            | "on"
+            | "off"
+            | "early"
+            | "delay"
+            | "rise"
+            | "fall"
+            | "power on"
+            | "power off"
+            | "index"
+            | "feeder_mode"
+            | "out_speed"
+            | "in_speed"
+            | "len"
+            | "feeder_cycle"
+            | "smoothness"
+            | "out_delay"
+            | "in_delay"
+            | "out_len"
+            | "in_len"
+    )
+}
+
+/// Diagnostics-collecting twin of `parse_process_like`: same line shapes,
+/// but every segment is checked for an unknown key, a non-numeric value, a
+/// repeated field, or (for `feeder_mode` lines) a `len` with no preceding
+/// `out_speed`/`in_speed`.
+fn parse_process_like_verbose(lines: &[String]) -> (ProcessParams, Vec<Diagnostic>) {
+    let mut p = ProcessParams::default();
+    let mut diags = Vec::new();
+    let mut seen_fields: HashSet<&'static str> = HashSet::new();
+
+    let mut set = |field: &'static str,
+                   value: &str,
+                   line: usize,
+                   diags: &mut Vec<Diagnostic>,
+                   slot: &mut Option<i64>| {
+        if !seen_fields.insert(field) {
+            diags.push(Diagnostic {
+                line,
+                key: Some(field.to_string()),
+                value: Some(value.to_string()),
+                reason: DiagnosticReason::DuplicateField,
+            });
+        }
+        match value.trim().parse::<i64>() {
+            Ok(n) => *slot = Some(n),
+            Err(_) => diags.push(Diagnostic {
+                line,
+                key: Some(field.to_string()),
+                value: Some(value.to_string()),
+                reason: DiagnosticReason::IntegerParseFailure,
+            }),
+        }
+    };
+
+    for (idx, raw) in lines.iter().enumerate() {
+        let s = raw.trim();
+
+        let mut expect_out_len = false;
+        let mut expect_in_len = false;
+        let is_feeder_mode_line = s.starts_with("feeder_mode:");
+
+        for part in s.split(',') {
+            let Some((k, v)) = part.split_once(':') else {
+                continue;
+            };
+            let key = k.trim();
+            let value = v.trim();
+
+            let slot = match key {
+                "power" => Some(("power", &mut p.power)),
+                "fre" if s.starts_with("power:") => Some(("pwm_fre", &mut p.pwm_fre)),
+                "fre" if s.starts_with("head mode:") => Some(("head_fre", &mut p.head_fre)),
+                "duty" => Some(("pwm_duty", &mut p.pwm_duty)),
+                "mode" => Some(("mode", &mut p.mode)),
+                // The multi-word line shapes below glue their prefix onto
+                // the first `key:value` segment's key when the whole line is
+                // split on ',' (unlike `parse_process_like`, which strips the
+                // literal prefix text first); match the glued key directly
+                // instead of re-deriving the prefix-stripped split.
+                "head mode" => Some(("head_mode", &mut p.head_mode)),
+                "width" => Some(("head_width", &mut p.head_width)),
+                "pulse tick on" => Some(("pulse_on", &mut p.pulse_on)),
+                "on" if s.starts_with("pulse tick") => Some(("pulse_on", &mut p.pulse_on)),
+                "off" if s.starts_with("pulse tick") => Some(("pulse_off", &mut p.pulse_off)),
+                "gas tick early" => Some(("gas_early", &mut p.gas_early)),
+                "early" if s.starts_with("gas tick") => Some(("gas_early", &mut p.gas_early)),
+                "delay" if s.starts_with("gas tick") => Some(("gas_delay", &mut p.gas_delay)),
+                "power tick rise" => Some(("pow_rise", &mut p.pow_rise)),
+                "rise" => Some(("pow_rise", &mut p.pow_rise)),
+                "fall" => Some(("pow_fall", &mut p.pow_fall)),
+                "early" if s.starts_with("power tick") => Some(("pow_early", &mut p.pow_early)),
+                "delay" if s.starts_with("power tick") => Some(("pow_delay", &mut p.pow_delay)),
+                "power on" => Some(("power_on", &mut p.power_on)),
+                "power off" => Some(("power_off", &mut p.power_off)),
+                "process index" => Some(("index", &mut p.index)),
+                "index" => Some(("index", &mut p.index)),
+                "feeder_mode" => Some(("feeder_mode", &mut p.feeder_mode)),
+                "out_speed" => {
+                    expect_out_len = true;
+                    expect_in_len = false;
+                    Some(("feeder_out_speed", &mut p.feeder_out_speed))
+                }
+                "in_speed" => {
+                    expect_in_len = true;
+                    expect_out_len = false;
+                    Some(("feeder_in_speed", &mut p.feeder_in_speed))
+                }
+                "len" if is_feeder_mode_line && expect_out_len => {
+                    expect_out_len = false;
+                    Some(("feeder_out_len", &mut p.feeder_out_len))
+                }
+                "len" if is_feeder_mode_line && expect_in_len => {
+                    expect_in_len = false;
+                    Some(("feeder_in_len", &mut p.feeder_in_len))
+                }
+                "len" if is_feeder_mode_line => {
+                    diags.push(Diagnostic {
+                        line: idx,
+                        key: Some(key.to_string()),
+                        value: Some(value.to_string()),
+                        reason: DiagnosticReason::DanglingLen,
+                    });
+                    None
+                }
+                "feeder_cycle" => Some(("feeder_cycle", &mut p.feeder_cycle)),
+                "smoothness" => Some(("feeder_smoothness", &mut p.feeder_smoothness)),
+                "out_delay" => Some(("feeder_out_delay", &mut p.feeder_out_delay)),
+                "in_delay" => Some(("feeder_in_delay", &mut p.feeder_in_delay)),
+                "out_len" => Some(("feeder_out_len", &mut p.feeder_out_len)),
+                "in_len" => Some(("feeder_in_len", &mut p.feeder_in_len)),
+                _ => {
+                    if !known_field(key) {
+                        diags.push(Diagnostic {
+                            line: idx,
+                            key: Some(key.to_string()),
+                            value: Some(value.to_string()),
+                            reason: DiagnosticReason::UnknownKey,
+                        });
+                    }
+                    None
+                }
+            };
+
+            if let Some((field, target)) = slot {
+                set(field, value, idx, &mut diags, target);
+            }
+        }
+    }
+
+    (p, diags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feeder_cycle_line_out_len_in_len_match_lenient_parse() {
+        let lines = vec!["feeder_cycle:400,out_len:13,in_len:14".to_string()];
+        let (msg, diags) = parse_verbose("feeder_pro", &lines);
+        match msg {
+            ParsedMessage::ProcessFeeder(p) => {
+                assert_eq!(p.feeder_cycle, Some(400));
+                assert_eq!(p.feeder_out_len, Some(13));
+                assert_eq!(p.feeder_in_len, Some(14));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+        assert!(diags.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_key_reported() {
+        let lines = vec!["power:100,frobnicate:1".to_string()];
+        let (_, diags) = parse_verbose("cur_pro", &lines);
+        assert!(diags
+            .iter()
+            .any(|d| d.reason == DiagnosticReason::UnknownKey && d.key.as_deref() == Some("frobnicate")));
+    }
+
+    #[test]
+    fn test_integer_parse_failure_reported() {
+        let lines = vec!["power:notanumber".to_string()];
+        let (msg, diags) = parse_verbose("cur_pro", &lines);
+        match msg {
+            ParsedMessage::ProcessCur(p) => assert_eq!(p.power, None),
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+        assert!(diags
+            .iter()
+            .any(|d| d.reason == DiagnosticReason::IntegerParseFailure));
+    }
+
+    #[test]
+    fn test_duplicate_field_reported() {
+        let lines = vec!["power:100".to_string(), "power:200".to_string()];
+        let (_, diags) = parse_verbose("cur_pro", &lines);
+        assert!(diags
+            .iter()
+            .any(|d| d.reason == DiagnosticReason::DuplicateField));
+    }
+
+    #[test]
+    fn test_dangling_len_reported() {
+        let lines = vec!["feeder_mode:0,len:13".to_string()];
+        let (_, diags) = parse_verbose("feeder_pro", &lines);
+        assert!(diags
+            .iter()
+            .any(|d| d.reason == DiagnosticReason::DanglingLen));
+    }
+
+    /// `parse_process_like_verbose` is a hand-maintained parallel copy of
+    /// `parser::parse_process_like`'s line-shape dispatch, not a shared
+    /// implementation, so the two can silently drift apart as line shapes
+    /// change. Diff their `ProcessParams` output across the same fixtures on
+    /// every clean (no-diagnostic) input so drift fails a test instead of
+    /// surfacing as a production mismatch between `parse` and `parse_verbose`.
+    #[test]
+    fn test_parse_and_parse_verbose_agree_on_clean_fixtures() {
+        let fixtures: Vec<Vec<String>> = vec![
+            vec![
+                "power:100,fre:3000,duty:100,mode:0".to_string(),
+                "head mode:1,fre:8,width:80".to_string(),
+                "pulse tick on:150,off:150".to_string(),
+                "gas tick early:200,delay:150".to_string(),
+                "power tick rise:100,fall:50,early:0,delay:200".to_string(),
+                "power on:0, power off:0".to_string(),
+                "process index:0".to_string(),
+            ],
+            vec![
+                "feeder_mode:0,out_speed:10,len:13,in_speed:20,len:14".to_string(),
+                "feeder_cycle:400, smoothness:40,out_delay:0,in_delay:400".to_string(),
+            ],
+            vec!["feeder_cycle:400,out_len:13,in_len:14".to_string()],
+        ];
+
+        for lines in fixtures {
+            for cmd in ["cur_pro", "feeder_pro"] {
+                let lenient = match parser::parse(cmd, &lines) {
+                    ParsedMessage::ProcessCur(p) | ParsedMessage::ProcessFeeder(p) => p,
+                    other => panic!("unexpected parse result: {:?}", other),
+                };
+                let (verbose_msg, diags) = parse_verbose(cmd, &lines);
+                let verbose = match verbose_msg {
+                    ParsedMessage::ProcessCur(p) | ParsedMessage::ProcessFeeder(p) => p,
+                    other => panic!("unexpected parse_verbose result: {:?}", other),
+                };
+                assert!(diags.is_empty(), "unexpected diagnostics for {cmd} on {lines:?}: {diags:?}");
+                assert_eq!(
+                    lenient, verbose,
+                    "parse and parse_verbose diverged for {cmd} on {lines:?}"
+                );
+            }
+        }
+    }
+}
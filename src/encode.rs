@@ -0,0 +1,253 @@
+//! Encoders for [`ParsedMessage`] beyond the default JSON `Serialize` impl.
+//!
+//! `Json` and `MsgPack` are thin wrappers over serde; `LineProtocol` is a
+//! purpose-built flattener that turns the numeric variants into
+//! `measurement field=value,...` lines suitable for ingestion by a
+//! time-series database.
+
+use anyhow::{anyhow, Result};
+
+use crate::parser::{ParsedMessage, PortLine, ProcessParams, SubStatus, ThreadRow};
+
+/// Output format selector for [`encode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MsgPack,
+    LineProtocol,
+}
+
+/// Encode a parsed message in the requested wire format.
+pub fn encode(msg: &ParsedMessage, enc: Encoding) -> Result<Vec<u8>> {
+    match enc {
+        Encoding::Json => Ok(serde_json::to_vec(msg)?),
+        Encoding::MsgPack => Ok(rmp_serde::to_vec(msg)?),
+        Encoding::LineProtocol => Ok(to_line_protocol(msg)?.into_bytes()),
+    }
+}
+
+/// Flatten a [`ParsedMessage`] into a single InfluxDB-style line protocol
+/// line (`Ps` rows and `ReadAll` ports become suffixed fields of that line).
+/// `None` fields are skipped; hex port values are emitted as integers.
+fn to_line_protocol(msg: &ParsedMessage) -> Result<String> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    let measurement = match msg {
+        ParsedMessage::Free(f) => {
+            fields.push(("total".into(), f.total.to_string()));
+            fields.push(("used".into(), f.used.to_string()));
+            fields.push(("maximum".into(), f.maximum.to_string()));
+            "free"
+        }
+        ParsedMessage::ProcessCur(p) => {
+            push_process_fields(&mut fields, p);
+            "process_cur"
+        }
+        ParsedMessage::ProcessFeeder(p) => {
+            push_process_fields(&mut fields, p);
+            "process_feeder"
+        }
+        ParsedMessage::SubStatus(s) => {
+            push_substatus_fields(&mut fields, s);
+            "sub_status"
+        }
+        ParsedMessage::Ps { rows, .. } => {
+            for (i, row) in rows.iter().enumerate() {
+                push_thread_row_fields(&mut fields, i, row);
+            }
+            "ps"
+        }
+        ParsedMessage::ReadAll(ports) => {
+            for port in ports {
+                push_port_line_fields(&mut fields, port);
+            }
+            "read_all"
+        }
+        other => {
+            return Err(anyhow!(
+                "line protocol encoding is not supported for this variant: {:?}",
+                other
+            ))
+        }
+    };
+
+    if fields.is_empty() {
+        return Ok(format!("{measurement}\n"));
+    }
+
+    let field_str = fields
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    Ok(format!("{measurement} {field_str}\n"))
+}
+
+fn push_process_fields(fields: &mut Vec<(String, String)>, p: &ProcessParams) {
+    macro_rules! push_opt {
+        ($name:ident) => {
+            if let Some(v) = p.$name {
+                fields.push((stringify!($name).to_string(), v.to_string()));
+            }
+        };
+    }
+    push_opt!(power);
+    push_opt!(pwm_fre);
+    push_opt!(pwm_duty);
+    push_opt!(mode);
+    push_opt!(head_mode);
+    push_opt!(head_fre);
+    push_opt!(head_width);
+    push_opt!(pulse_on);
+    push_opt!(pulse_off);
+    push_opt!(gas_early);
+    push_opt!(gas_delay);
+    push_opt!(pow_rise);
+    push_opt!(pow_fall);
+    push_opt!(pow_early);
+    push_opt!(pow_delay);
+    push_opt!(power_on);
+    push_opt!(power_off);
+    push_opt!(index);
+    push_opt!(feeder_mode);
+    push_opt!(feeder_out_speed);
+    push_opt!(feeder_out_len);
+    push_opt!(feeder_in_speed);
+    push_opt!(feeder_in_len);
+    push_opt!(feeder_cycle);
+    push_opt!(feeder_smoothness);
+    push_opt!(feeder_out_delay);
+    push_opt!(feeder_in_delay);
+}
+
+fn push_substatus_fields(fields: &mut Vec<(String, String)>, s: &SubStatus) {
+    if let Some(v) = s.laser_sub_state {
+        fields.push(("laser_sub_state".into(), v.to_string()));
+    }
+    if let Some(v) = s.feeder_state {
+        fields.push(("feeder_state".into(), v.to_string()));
+    }
+    if let Some(v) = s.gas_state {
+        fields.push(("gas_state".into(), v.to_string()));
+    }
+}
+
+fn push_thread_row_fields(fields: &mut Vec<(String, String)>, idx: usize, row: &ThreadRow) {
+    macro_rules! push_opt {
+        ($name:ident) => {
+            if let Some(v) = row.$name {
+                fields.push((format!("{}_{idx}", stringify!($name)), v.to_string()));
+            }
+        };
+    }
+    push_opt!(pri);
+    push_opt!(used);
+    push_opt!(left);
+    push_opt!(tick);
+    push_opt!(error);
+}
+
+fn push_port_line_fields(fields: &mut Vec<(String, String)>, port: &PortLine) {
+    if let Some(v) = parse_hex_port(&port.input) {
+        fields.push((format!("{}_in", port.port), v.to_string()));
+    }
+    if let Some(v) = parse_hex_port(&port.output) {
+        fields.push((format!("{}_out", port.port), v.to_string()));
+    }
+}
+
+fn parse_hex_port(raw: &str) -> Option<i64> {
+    let digits: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    let digits = digits.strip_prefix("0x").unwrap_or(&digits);
+    i64::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Free;
+
+    #[test]
+    fn test_json_encodes_tagged_variant() {
+        let msg = ParsedMessage::Free(Free {
+            total: 64424,
+            used: 60776,
+            maximum: 60916,
+        });
+        let bytes = encode(&msg, Encoding::Json).unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(decoded["kind"], "free");
+        assert_eq!(decoded["total"], 64424);
+    }
+
+    #[test]
+    fn test_msgpack_encodes_without_error() {
+        let msg = ParsedMessage::Free(Free {
+            total: 1,
+            used: 2,
+            maximum: 3,
+        });
+        let bytes = encode(&msg, Encoding::MsgPack).unwrap();
+        assert!(!bytes.is_empty());
+    }
+
+    #[test]
+    fn test_line_protocol_free_includes_all_fields() {
+        let msg = ParsedMessage::Free(Free {
+            total: 10,
+            used: 5,
+            maximum: 10,
+        });
+        let line = to_line_protocol(&msg).unwrap();
+        assert_eq!(line, "free total=10,used=5,maximum=10\n");
+    }
+
+    #[test]
+    fn test_line_protocol_process_params_skips_none_fields() {
+        let p = ProcessParams {
+            power: Some(100),
+            ..Default::default()
+        };
+        let msg = ParsedMessage::ProcessCur(p);
+        let line = to_line_protocol(&msg).unwrap();
+        assert_eq!(line, "process_cur power=100\n");
+    }
+
+    #[test]
+    fn test_line_protocol_process_params_all_none_emits_bare_measurement() {
+        let msg = ParsedMessage::ProcessCur(ProcessParams::default());
+        let line = to_line_protocol(&msg).unwrap();
+        assert_eq!(line, "process_cur\n");
+    }
+
+    #[test]
+    fn test_line_protocol_unsupported_variant_errors() {
+        let msg = ParsedMessage::Unknown {
+            cmd: "whoami".to_string(),
+            lines: vec![],
+        };
+        assert!(to_line_protocol(&msg).is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_port_accepts_0x_prefix_and_whitespace() {
+        assert_eq!(parse_hex_port("0x1F"), Some(31));
+        assert_eq!(parse_hex_port(" 1F "), Some(31));
+    }
+
+    #[test]
+    fn test_parse_hex_port_rejects_non_hex() {
+        assert_eq!(parse_hex_port("not hex"), None);
+    }
+
+    #[test]
+    fn test_push_port_line_fields_skips_unparseable_side() {
+        let mut fields = Vec::new();
+        let port = PortLine {
+            port: "p1".to_string(),
+            input: "0x0A".to_string(),
+            output: "n/a".to_string(),
+        };
+        push_port_line_fields(&mut fields, &port);
+        assert_eq!(fields, vec![("p1_in".to_string(), "10".to_string())]);
+    }
+}
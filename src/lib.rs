@@ -0,0 +1,15 @@
+pub mod client;
+pub mod diagnostics;
+pub mod encode;
+pub mod parser;
+pub mod schema;
+pub mod stream;
+pub mod worktime_log;
+
+pub use client::{AsyncClient, Client, MshClient, SyncClient};
+pub use diagnostics::{parse_verbose, Diagnostic, DiagnosticReason};
+pub use encode::{encode, Encoding};
+pub use parser::{parse, ParsedMessage};
+pub use schema::{ParserRegistry, SchemaTable};
+pub use stream::StreamParser;
+pub use worktime_log::WorkTimeLog;
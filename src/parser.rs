@@ -76,30 +76,254 @@ pub struct ThreadRow {
     pub error: Option<i32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct WorkTime {
     #[serde(rename = "Start date")]
-    pub start_date: Option<String>,
+    pub start_date: Option<WorkTimeDate>,
     #[serde(rename = "End date")]
-    pub end_date: Option<String>,
+    pub end_date: Option<WorkTimeDate>,
     #[serde(rename = "Current date")]
-    pub current_date: Option<String>,
+    pub current_date: Option<WorkTimeDate>,
     #[serde(rename = "Total startup time")]
-    pub total_startup_time: Option<String>,
+    pub total_startup_time: Option<WorkTimeDuration>,
     #[serde(rename = "Current startup time")]
-    pub current_startup_time: Option<String>,
+    pub current_startup_time: Option<WorkTimeDuration>,
     #[serde(rename = "onkey time")]
-    pub onkey_time: Option<String>,
+    pub onkey_time: Option<WorkTimeDuration>,
     #[serde(rename = "decodeTimes")]
     pub decode_times: Option<i64>,
     #[serde(rename = "Days available")]
     pub days_available: Option<i64>,
 }
 
+/// A `WorkTime` date field: parsed to a real timestamp when it matches the
+/// tolerated `"YYYY-MM-DD HH:MM:SS"` shape, otherwise the raw device string.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum WorkTimeDate {
+    Parsed(chrono::NaiveDateTime),
+    Raw(String),
+}
+
+impl WorkTimeDate {
+    pub fn as_parsed(&self) -> Option<chrono::NaiveDateTime> {
+        match self {
+            WorkTimeDate::Parsed(dt) => Some(*dt),
+            WorkTimeDate::Raw(_) => None,
+        }
+    }
+}
+
+/// A `WorkTime` elapsed-time field (e.g. `"Total startup time"`), parsed to
+/// a `chrono::Duration` when it matches the tolerated `H:MM:SS` shape,
+/// otherwise the raw device string.
+#[derive(Debug, Clone)]
+pub enum WorkTimeDuration {
+    Parsed(chrono::Duration),
+    Raw(String),
+}
+
+impl WorkTimeDuration {
+    pub fn as_parsed(&self) -> Option<chrono::Duration> {
+        match self {
+            WorkTimeDuration::Parsed(d) => Some(*d),
+            WorkTimeDuration::Raw(_) => None,
+        }
+    }
+}
+
+impl Serialize for WorkTimeDuration {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            WorkTimeDuration::Parsed(d) => serializer.serialize_i64(d.num_seconds()),
+            WorkTimeDuration::Raw(s) => serializer.serialize_str(s),
+        }
+    }
+}
+
+/// Parse the tolerant `"YYYY-MM-DD HH:MM:SS"` shape (missing seconds and
+/// leading/trailing junk are allowed), falling back to the raw string.
+fn parse_worktime_date(raw: &str) -> WorkTimeDate {
+    let trimmed = raw.trim();
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S") {
+        return WorkTimeDate::Parsed(dt);
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return WorkTimeDate::Parsed(date.and_hms_opt(0, 0, 0).unwrap());
+    }
+    // Missing seconds, e.g. "2024-01-01 10:30".
+    if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M") {
+        return WorkTimeDate::Parsed(dt);
+    }
+    // Tolerate junk around the recognizable "YYYY-MM-DD[ HH:MM[:SS]]" core.
+    let core: String = trimmed
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '-' || *c == ':' || *c == ' ')
+        .collect();
+    let core = core.trim();
+    if core != trimmed {
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(core, "%Y-%m-%d %H:%M:%S") {
+            return WorkTimeDate::Parsed(dt);
+        }
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(core, "%Y-%m-%d") {
+            return WorkTimeDate::Parsed(date.and_hms_opt(0, 0, 0).unwrap());
+        }
+        if let Ok(dt) = chrono::NaiveDateTime::parse_from_str(&format!("{core}:00"), "%Y-%m-%d %H:%M:%S")
+        {
+            return WorkTimeDate::Parsed(dt);
+        }
+    }
+    WorkTimeDate::Raw(raw.to_string())
+}
+
+/// Parse an elapsed-time field shaped like `"H:MM:SS"` (or `"H:MM"`),
+/// falling back to the raw string.
+fn parse_worktime_duration(raw: &str) -> WorkTimeDuration {
+    let trimmed = raw.trim();
+    let parts: Vec<&str> = trimmed.split(':').collect();
+    let parsed = match parts.as_slice() {
+        [h, m, s] => match (h.parse::<i64>(), m.parse::<i64>(), s.parse::<i64>()) {
+            (Ok(h), Ok(m), Ok(s)) => Some(h * 3600 + m * 60 + s),
+            _ => None,
+        },
+        [h, m] => match (h.parse::<i64>(), m.parse::<i64>()) {
+            (Ok(h), Ok(m)) => Some(h * 3600 + m * 60),
+            _ => None,
+        },
+        _ => None,
+    };
+    match parsed {
+        Some(secs) => WorkTimeDuration::Parsed(chrono::Duration::seconds(secs)),
+        None => WorkTimeDuration::Raw(raw.to_string()),
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct FlagWord {
     pub raw: String,    // e.g. "0x002000001"
     pub labels: String, // tail text, e.g. "INTERLOCK GND_LOCK"
+    /// Decoded, severity-ranked view of the set bits in `raw`.
+    pub active_flags: Vec<ActiveFlag>,
+    /// Highest severity across `active_flags`, so callers can alarm without re-scanning.
+    pub highest_severity: Option<Severity>,
+}
+
+/// Severity of a single decoded flag bit.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A known bit position in a FlagWord bitmask.
+#[derive(Debug, Clone, Copy)]
+pub struct FlagBit {
+    pub bit: u8,
+    pub mask: u64,
+    pub name: &'static str,
+    pub severity: Severity,
+}
+
+/// One set bit, resolved against the known bit table (or `UNKNOWN_BIT_n`).
+#[derive(Debug, Serialize, Clone)]
+pub struct ActiveFlag {
+    pub bit: u8,
+    pub name: String,
+    pub severity: Severity,
+}
+
+/// Known FlagWord bit positions, cross-referenced against the trailing label tokens.
+static FLAG_BITS: &[FlagBit] = &[
+    FlagBit {
+        bit: 0,
+        mask: 1 << 0,
+        name: "INTERLOCK",
+        severity: Severity::Error,
+    },
+    FlagBit {
+        bit: 1,
+        mask: 1 << 1,
+        name: "GND_LOCK",
+        severity: Severity::Error,
+    },
+    FlagBit {
+        bit: 2,
+        mask: 1 << 2,
+        name: "WATER_FLOW",
+        severity: Severity::Error,
+    },
+    FlagBit {
+        bit: 3,
+        mask: 1 << 3,
+        name: "WATER_TEMP",
+        severity: Severity::Warning,
+    },
+    FlagBit {
+        bit: 4,
+        mask: 1 << 4,
+        name: "GAS_PRESSURE",
+        severity: Severity::Warning,
+    },
+    FlagBit {
+        bit: 5,
+        mask: 1 << 5,
+        name: "DOOR_OPEN",
+        severity: Severity::Error,
+    },
+    FlagBit {
+        bit: 6,
+        mask: 1 << 6,
+        name: "E_STOP",
+        severity: Severity::Error,
+    },
+    FlagBit {
+        bit: 21,
+        mask: 1 << 21,
+        name: "LASER_ON",
+        severity: Severity::Info,
+    },
+];
+
+/// Decode a `0x...` FlagWord bitmask into structured, severity-ranked flags.
+fn decode_flag_bits(raw: &str) -> Vec<ActiveFlag> {
+    let value = raw
+        .trim()
+        .strip_prefix("0x")
+        .or_else(|| raw.trim().strip_prefix("0X"))
+        .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+        .unwrap_or(0);
+
+    let mut flags = Vec::new();
+    for bit in 0u8..64 {
+        let mask = 1u64 << bit;
+        if value & mask == 0 {
+            continue;
+        }
+        if let Some(known) = FLAG_BITS.iter().find(|f| f.mask == mask) {
+            flags.push(ActiveFlag {
+                bit,
+                name: known.name.to_string(),
+                severity: known.severity,
+            });
+        } else {
+            // No table entry for this bit: emit a generic placeholder rather
+            // than guessing a name from the trailing label text, which has
+            // no reliable correspondence to bit position and would collide
+            // two distinct unknown bits onto the same label.
+            let name = format!("UNKNOWN_BIT_{bit}");
+            flags.push(ActiveFlag {
+                bit,
+                name,
+                severity: Severity::Warning,
+            });
+        }
+    }
+    flags
 }
 
 #[derive(Debug, Serialize)]
@@ -115,7 +339,7 @@ pub struct SubStatus {
     pub extras: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct KVLine {
     pub key: String,
     pub value: String,
@@ -157,7 +381,7 @@ pub struct PortLine {
 
 /// Unified process parameter set parsed from `cur_pro` and `feeder_pro`.
 /// We keep everything in one struct so upstream can render a single JSON doc.
-#[derive(Debug, Serialize, Default, Clone)]
+#[derive(Debug, Serialize, Default, Clone, PartialEq)]
 pub struct ProcessParams {
     // Main PWM/power controls
     pub power: Option<i64>,    // %
@@ -278,14 +502,16 @@ pub fn parse(cmd: &str, lines: &[String]) -> ParsedMessage {
 
 /* ===================== helpers and specific parsers ===================== */
 
-fn normalize_line(s: &str) -> String {
+pub(crate) fn normalize_line(s: &str) -> String {
     let mut t = s.replace('\r', "");
     // trim and collapse weird spacing tails
     t = t.trim_end().to_string();
     t
 }
 
-fn strip_prompt_noise(lines: &mut Vec<String>) {
+pub(crate) const PROMPT: &str = "msh >";
+
+pub(crate) fn strip_prompt_noise(lines: &mut Vec<String>) {
     // Drop known banners / prompt echoes
     let _prefixes = [
         "RT-Thread shell commands:",
@@ -307,7 +533,7 @@ fn take_after_colon(s: &str) -> Option<String> {
     s.split_once(':').map(|(_, right)| right.trim().to_string())
 }
 
-fn split_kv(line: &str) -> Option<KVLine> {
+pub(crate) fn split_kv(line: &str) -> Option<KVLine> {
     let (k, v) = line.split_once(':')?;
     Some(KVLine {
         key: k.trim().to_string(),
@@ -454,12 +680,16 @@ fn parse_worktime(lines: &[String]) -> Result<ParsedMessage> {
     for l in lines {
         if let Some(kv) = split_kv(l) {
             match kv.key.as_str() {
-                "Start date" => wt.start_date = Some(kv.value.to_string()),
-                "End date" => wt.end_date = Some(kv.value.to_string()),
-                "Current date" => wt.current_date = Some(kv.value.to_string()),
-                "Total startup time" => wt.total_startup_time = Some(kv.value.to_string()),
-                "Current startup time" => wt.current_startup_time = Some(kv.value.to_string()),
-                "onkey time" => wt.onkey_time = Some(kv.value.to_string()),
+                "Start date" => wt.start_date = Some(parse_worktime_date(&kv.value)),
+                "End date" => wt.end_date = Some(parse_worktime_date(&kv.value)),
+                "Current date" => wt.current_date = Some(parse_worktime_date(&kv.value)),
+                "Total startup time" => {
+                    wt.total_startup_time = Some(parse_worktime_duration(&kv.value))
+                }
+                "Current startup time" => {
+                    wt.current_startup_time = Some(parse_worktime_duration(&kv.value))
+                }
+                "onkey time" => wt.onkey_time = Some(parse_worktime_duration(&kv.value)),
                 "decodeTimes" => wt.decode_times = kv.value.parse::<i64>().ok(),
                 "Days available" => wt.days_available = kv.value.parse::<i64>().ok(),
                 _ => {}
@@ -481,7 +711,14 @@ fn parse_flagword(_prefix: &str, lines: &[String]) -> Result<ParsedMessage> {
         .name("labels")
         .map(|m| m.as_str().to_string())
         .unwrap_or_default();
-    let fw = FlagWord { raw, labels };
+    let active_flags = decode_flag_bits(&raw);
+    let highest_severity = active_flags.iter().map(|f| f.severity).max();
+    let fw = FlagWord {
+        raw,
+        labels,
+        active_flags,
+        highest_severity,
+    };
     Ok(match _prefix {
         "WARNING" => ParsedMessage::Warning(fw),
         "ERROR" => ParsedMessage::Error(fw),
@@ -509,15 +746,25 @@ fn parse_substatus(lines: &[String]) -> ParsedMessage {
         extras: Vec::new(),
     };
     for l in lines {
+        let mut prev: Option<&str> = None;
         for token in l.split_whitespace() {
             if let Some(v) = capture_num(token, "laser_sub_state(") {
                 s.laser_sub_state = Some(v);
             } else if let Some(v) = capture_num(token, "state(") {
-                // ambiguous; we only set feeder/gas if unknown
-                if s.feeder_state.is_none() {
-                    s.feeder_state = Some(v);
-                } else if s.gas_state.is_none() {
-                    s.gas_state = Some(v);
+                // The word immediately before "state(" tells us which field
+                // this is ("feeder state(0)" / "gas state(0)"); fall back to
+                // the old ambiguous first-unset-wins order only if that word
+                // is missing or unrecognized.
+                match prev {
+                    Some("feeder") => s.feeder_state = Some(v),
+                    Some("gas") => s.gas_state = Some(v),
+                    _ => {
+                        if s.feeder_state.is_none() {
+                            s.feeder_state = Some(v);
+                        } else if s.gas_state.is_none() {
+                            s.gas_state = Some(v);
+                        }
+                    }
                 }
             } else {
                 // keep interesting leftovers
@@ -525,6 +772,7 @@ fn parse_substatus(lines: &[String]) -> ParsedMessage {
                     s.extras.push(token.to_string());
                 }
             }
+            prev = Some(token);
         }
     }
     ParsedMessage::SubStatus(s)
@@ -635,6 +883,130 @@ fn parse_readall(lines: &[String]) -> ParsedMessage {
     ParsedMessage::ReadAll(out)
 }
 
+/* -------- NEW: inverse encoder, ProcessParams -> device protocol lines -------- */
+
+impl ProcessParams {
+    /// Reproduce the exact grouping and comma/colon layout the device
+    /// expects for `cur_pro`/`feeder_pro`-style configuration, the inverse
+    /// of [`parse_process_like`]. The `power:`, `head mode:` and
+    /// `feeder_mode:` lines are emitted only when their leading field is set;
+    /// the `feeder_cycle:` line only when the cycle, the smoothness or (with
+    /// no `feeder_mode:` line) a len is set; every other line when any field
+    /// of its group is set. Fields of an omitted line and `extras` are not
+    /// written, so `parse(cmd, &p.to_protocol_lines())` round-trips only
+    /// parameter sets that have no such fields.
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        if self.power.is_some() {
+            lines.push(format!(
+                "power:{},fre:{},duty:{},mode:{}",
+                opt(self.power),
+                opt(self.pwm_fre),
+                opt(self.pwm_duty),
+                opt(self.mode),
+            ));
+        }
+
+        if self.head_mode.is_some() {
+            let mut parts = vec![format!("head mode:{}", opt(self.head_mode))];
+            if let Some(v) = self.head_fre {
+                parts.push(format!("fre:{v}"));
+            }
+            if let Some(v) = self.head_width {
+                parts.push(format!("width:{v}"));
+            }
+            lines.push(parts.join(","));
+        }
+
+        if self.pulse_on.is_some() || self.pulse_off.is_some() {
+            lines.push(format!(
+                "pulse tick on:{},off:{}",
+                opt(self.pulse_on),
+                opt(self.pulse_off),
+            ));
+        }
+
+        if self.gas_early.is_some() || self.gas_delay.is_some() {
+            lines.push(format!(
+                "gas tick early:{},delay:{}",
+                opt(self.gas_early),
+                opt(self.gas_delay),
+            ));
+        }
+
+        if self.pow_rise.is_some()
+            || self.pow_fall.is_some()
+            || self.pow_early.is_some()
+            || self.pow_delay.is_some()
+        {
+            lines.push(format!(
+                "power tick rise:{},fall:{},early:{},delay:{}",
+                opt(self.pow_rise),
+                opt(self.pow_fall),
+                opt(self.pow_early),
+                opt(self.pow_delay),
+            ));
+        }
+
+        if self.power_on.is_some() || self.power_off.is_some() {
+            lines.push(format!(
+                "power on:{}, power off:{}",
+                opt(self.power_on),
+                opt(self.power_off),
+            ));
+        }
+
+        if let Some(index) = self.index {
+            lines.push(format!("process index:{index}"));
+        }
+
+        if self.feeder_mode.is_some() {
+            lines.push(format!(
+                "feeder_mode:{},out_speed:{},len:{},in_speed:{},len:{}",
+                opt(self.feeder_mode),
+                opt(self.feeder_out_speed),
+                opt(self.feeder_out_len),
+                opt(self.feeder_in_speed),
+                opt(self.feeder_in_len),
+            ));
+        }
+
+        if self.feeder_cycle.is_some()
+            || self.feeder_smoothness.is_some()
+            || (self.feeder_mode.is_none()
+                && (self.feeder_out_len.is_some() || self.feeder_in_len.is_some()))
+        {
+            let mut line = format!(
+                "feeder_cycle:{}, smoothness:{},out_delay:{},in_delay:{}",
+                opt(self.feeder_cycle),
+                opt(self.feeder_smoothness),
+                opt(self.feeder_out_delay),
+                opt(self.feeder_in_delay),
+            );
+            // `feeder_mode:`'s own "len:" fields already carry out/in len when
+            // that line is present; only the `feeder_cycle:` line shape can
+            // set them otherwise (see `parse_process_like`), so re-emit them
+            // here to round-trip that case.
+            if self.feeder_mode.is_none() {
+                if self.feeder_out_len.is_some() {
+                    line.push_str(&format!(",out_len:{}", opt(self.feeder_out_len)));
+                }
+                if self.feeder_in_len.is_some() {
+                    line.push_str(&format!(",in_len:{}", opt(self.feeder_in_len)));
+                }
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
+}
+
+fn opt(v: Option<i64>) -> String {
+    v.map(|n| n.to_string()).unwrap_or_default()
+}
+
 /* -------- NEW: process parser shared by cur_pro / feeder_pro -------- */
 
 fn parse_process_like(lines: &[String]) -> ProcessParams {
@@ -854,11 +1226,295 @@ fn parse_process_like(lines: &[String]) -> ProcessParams {
     p
 }
 
+/* -------- NEW: inverse encoders for the remaining variants -------- */
+
+impl KVLine {
+    fn to_protocol_line(&self) -> String {
+        format!("{}: {}", self.key, self.value)
+    }
+}
+
+impl Free {
+    /// Inverse of [`parse_free`].
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        vec![
+            format!("total   : {}", self.total),
+            format!("used    : {}", self.used),
+            format!("maximum : {}", self.maximum),
+        ]
+    }
+}
+
+impl IfConfig {
+    /// Inverse of [`parse_ifconfig`]. Fields that were never set are omitted.
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(v) = &self.iface {
+            lines.push(format!("network interface device: {v}"));
+        }
+        if let Some(v) = self.mtu {
+            lines.push(format!("MTU: {v}"));
+        }
+        if let Some(v) = &self.mac {
+            lines.push(format!("MAC: {v}"));
+        }
+        if let Some(v) = &self.flags {
+            lines.push(format!("FLAGS: {v}"));
+        }
+        if let Some(v) = &self.ip_addr {
+            lines.push(format!("ip address: {v}"));
+        }
+        if let Some(v) = &self.gw_addr {
+            lines.push(format!("gw address: {v}"));
+        }
+        if let Some(v) = &self.netmask {
+            lines.push(format!("net mask: {v}"));
+        }
+        if let Some(v) = &self.dns0 {
+            lines.push(format!("dns server #0: {v}"));
+        }
+        if let Some(v) = &self.dns1 {
+            lines.push(format!("dns server #1: {v}"));
+        }
+        lines
+    }
+}
+
+impl Dns {
+    /// Inverse of [`parse_dns`].
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(v) = &self.iface {
+            lines.push(format!("network interface device: {v}"));
+        }
+        if let Some(v) = &self.dns0 {
+            lines.push(format!("dns server #0: {v}"));
+        }
+        if let Some(v) = &self.dns1 {
+            lines.push(format!("dns server #1: {v}"));
+        }
+        lines
+    }
+}
+
+impl Netstat {
+    /// Inverse of [`parse_netstat`]; device listing details other than the
+    /// port number itself aren't part of [`Netstat`], so the `state` field
+    /// is reconstructed as `LISTEN`.
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        self.listen_ports
+            .iter()
+            .map(|p| format!("local port  {p}  state  LISTEN"))
+            .collect()
+    }
+}
+
+impl PortLine {
+    fn to_protocol_line(&self) -> String {
+        format!("{} IN={}, OUT={}", self.port, self.input, self.output)
+    }
+}
+
+impl WorkTimeDate {
+    fn to_protocol_string(&self) -> String {
+        match self {
+            WorkTimeDate::Parsed(dt) => dt.format("%Y-%m-%d %H:%M:%S").to_string(),
+            WorkTimeDate::Raw(s) => s.clone(),
+        }
+    }
+}
+
+impl WorkTimeDuration {
+    fn to_protocol_string(&self) -> String {
+        match self {
+            WorkTimeDuration::Parsed(d) => {
+                let secs = d.num_seconds();
+                format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+            }
+            WorkTimeDuration::Raw(s) => s.clone(),
+        }
+    }
+}
+
+impl WorkTime {
+    /// Inverse of [`parse_worktime`]. Fields that were never reported are omitted.
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(v) = &self.start_date {
+            lines.push(format!("Start date: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = &self.end_date {
+            lines.push(format!("End date: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = &self.current_date {
+            lines.push(format!("Current date: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = &self.total_startup_time {
+            lines.push(format!("Total startup time: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = &self.current_startup_time {
+            lines.push(format!("Current startup time: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = &self.onkey_time {
+            lines.push(format!("onkey time: {}", v.to_protocol_string()));
+        }
+        if let Some(v) = self.decode_times {
+            lines.push(format!("decodeTimes: {v}"));
+        }
+        if let Some(v) = self.days_available {
+            lines.push(format!("Days available: {v}"));
+        }
+        lines
+    }
+}
+
+impl FlagWord {
+    /// Inverse of [`parse_flagword`]; `prefix` is `WARNING`/`ERROR`/`LOCK`,
+    /// which the [`ParsedMessage`] variant carries but [`FlagWord`] itself
+    /// doesn't store.
+    pub fn to_protocol_line(&self, prefix: &str) -> String {
+        format!("{prefix}({}) {}", self.raw, self.labels)
+    }
+}
+
+impl SubStatus {
+    /// Inverse of [`parse_substatus`].
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        if let Some(v) = self.laser_sub_state {
+            parts.push(format!("laser_sub_state({v})"));
+        }
+        if let Some(v) = self.feeder_state {
+            parts.push(format!("feeder state({v})"));
+        }
+        if let Some(v) = self.gas_state {
+            parts.push(format!("gas state({v})"));
+        }
+        parts.extend(self.extras.iter().cloned());
+        vec![parts.join(" ")]
+    }
+}
+
+impl ThreadRow {
+    fn to_protocol_line(&self) -> String {
+        let int_or_dash = |v: Option<i32>| v.map(|n| n.to_string()).unwrap_or_else(|| "-".into());
+        let str_or_dash = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".into());
+        format!(
+            "{} {} {} {} {} {} {} {} {} {} {}",
+            self.thread,
+            int_or_dash(self.pri),
+            self.status,
+            str_or_dash(&self.sp),
+            str_or_dash(&self.stack),
+            str_or_dash(&self.size),
+            str_or_dash(&self.max),
+            int_or_dash(self.used),
+            int_or_dash(self.left),
+            int_or_dash(self.tick),
+            int_or_dash(self.error),
+        )
+    }
+}
+
+impl ParsedMessage {
+    /// Reproduce device reply lines for a `ParsedMessage`, the inverse of
+    /// [`parse`]. Detail the parser discards is not restored, e.g.
+    /// [`Ps`](ParsedMessage::Ps)'s column widths, the `version` banner
+    /// lines, spacing around `:`, and the state of `netstat` rows (written
+    /// as `LISTEN`).
+    pub fn to_protocol_lines(&self) -> Vec<String> {
+        match self {
+            ParsedMessage::Version(v) => {
+                [&v.build, &v.copyright]
+                    .into_iter()
+                    .filter(|s| !s.is_empty())
+                    .cloned()
+                    .collect()
+            }
+            ParsedMessage::Free(f) => f.to_protocol_lines(),
+            ParsedMessage::Ps { rows, .. } => {
+                let mut lines = vec!["thread status".to_string(), "------".to_string()];
+                lines.extend(rows.iter().map(ThreadRow::to_protocol_line));
+                lines
+            }
+            ParsedMessage::WorkTime(wt) => wt.to_protocol_lines(),
+            ParsedMessage::Warning(fw) => vec![fw.to_protocol_line("WARNING")],
+            ParsedMessage::Error(fw) => vec![fw.to_protocol_line("ERROR")],
+            ParsedMessage::Lock(fw) => vec![fw.to_protocol_line("LOCK")],
+            ParsedMessage::Mode { mode } => vec![mode.clone()],
+            ParsedMessage::State { state } => vec![state.clone()],
+            ParsedMessage::Status(s) => s.lines.iter().map(KVLine::to_protocol_line).collect(),
+            ParsedMessage::SubStatus(s) => s.to_protocol_lines(),
+            ParsedMessage::IOState(lines) => lines.iter().map(KVLine::to_protocol_line).collect(),
+            ParsedMessage::IfConfig(c) => c.to_protocol_lines(),
+            ParsedMessage::Dns(d) => d.to_protocol_lines(),
+            ParsedMessage::Netstat(n) => n.to_protocol_lines(),
+            ParsedMessage::ReadAll(ports) => ports.iter().map(PortLine::to_protocol_line).collect(),
+            ParsedMessage::ProcessCur(p) => p.to_protocol_lines(),
+            ParsedMessage::ProcessFeeder(p) => p.to_protocol_lines(),
+            ParsedMessage::Unknown { lines, .. } => lines.clone(),
+        }
+    }
+}
+
 /* -------- tests (incl. process) -------- */
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_flagword_decodes_bits() {
+        let lines = vec!["ERROR(0x0000007) INTERLOCK GND_LOCK".to_string()];
+        match parse("error", &lines) {
+            ParsedMessage::Error(fw) => {
+                assert_eq!(fw.raw, "0x0000007");
+                assert_eq!(fw.highest_severity, Some(Severity::Error));
+                let names: Vec<&str> = fw.active_flags.iter().map(|f| f.name.as_str()).collect();
+                assert!(names.contains(&"INTERLOCK"));
+                assert!(names.contains(&"GND_LOCK"));
+                assert!(names.contains(&"WATER_FLOW"));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_flagword_unknown_bit() {
+        let lines = vec!["WARNING(0x0080000) ".to_string()];
+        match parse("warning", &lines) {
+            ParsedMessage::Warning(fw) => {
+                assert_eq!(fw.active_flags.len(), 1);
+                assert_eq!(fw.active_flags[0].name, "UNKNOWN_BIT_19");
+                assert_eq!(fw.highest_severity, Some(Severity::Warning));
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_worktime_date_tolerates_missing_seconds() {
+        match parse_worktime_date("2024-01-01 10:30") {
+            WorkTimeDate::Parsed(dt) => {
+                assert_eq!(dt.format("%Y-%m-%d %H:%M:%S").to_string(), "2024-01-01 10:30:00");
+            }
+            WorkTimeDate::Raw(s) => panic!("expected a parsed date, got raw: {s}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_flagword_distinct_unknown_bits_dont_collide() {
+        // bit 10 (0x400) and bit 20 (0x100000), neither in FLAG_BITS.
+        let lines = vec!["ERROR(0x100400) FOO_SENSOR BAR_SENSOR".to_string()];
+        match parse("error", &lines) {
+            ParsedMessage::Error(fw) => {
+                assert_eq!(fw.active_flags.len(), 2);
+                let names: Vec<&str> = fw.active_flags.iter().map(|f| f.name.as_str()).collect();
+                assert_eq!(names, vec!["UNKNOWN_BIT_10", "UNKNOWN_BIT_20"]);
+            }
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
     #[test]
     fn test_free() {
         let lines = vec![
@@ -931,4 +1587,138 @@ mod tests {
             other => panic!("unexpected feeder parse result: {:?}", other),
         }
     }
+
+    #[test]
+    fn test_process_params_round_trip() {
+        let cur = vec![
+            "power:100,fre:3000,duty:100,mode:0".to_string(),
+            "head mode:1,fre:8,width:80".to_string(),
+            "pulse tick on:150,off:150".to_string(),
+            "gas tick early:200,delay:150".to_string(),
+            "power tick rise:100,fall:50,early:0,delay:200".to_string(),
+            "power on:0, power off:0".to_string(),
+            "process index:0".to_string(),
+        ];
+        let first = match parse("cur_pro", &cur) {
+            ParsedMessage::ProcessCur(p) => p,
+            other => panic!("unexpected parse result: {:?}", other),
+        };
+
+        let re_encoded = first.to_protocol_lines();
+        let second = match parse("cur_pro", &re_encoded) {
+            ParsedMessage::ProcessCur(p) => p,
+            other => panic!("unexpected re-parse result: {:?}", other),
+        };
+
+        assert_eq!(first.power, second.power);
+        assert_eq!(first.head_width, second.head_width);
+        assert_eq!(first.pulse_on, second.pulse_on);
+        assert_eq!(first.pow_delay, second.pow_delay);
+        assert_eq!(first.power_on, second.power_on);
+        assert_eq!(first.index, second.index);
+
+        let feeder = vec![
+            "feeder_mode:0,out_speed:10,len:13,in_speed:20,len:14".to_string(),
+            "feeder_cycle:400, smoothness:40,out_delay:0,in_delay:400".to_string(),
+        ];
+        let first = match parse("feeder_pro", &feeder) {
+            ParsedMessage::ProcessFeeder(p) => p,
+            other => panic!("unexpected parse result: {:?}", other),
+        };
+        let second = match parse("feeder_pro", &first.to_protocol_lines()) {
+            ParsedMessage::ProcessFeeder(p) => p,
+            other => panic!("unexpected re-parse result: {:?}", other),
+        };
+        assert_eq!(first.feeder_out_len, second.feeder_out_len);
+        assert_eq!(first.feeder_in_len, second.feeder_in_len);
+        assert_eq!(first.feeder_cycle, second.feeder_cycle);
+    }
+
+    #[test]
+    fn test_process_params_round_trip_feeder_cycle_only_out_in_len() {
+        // No `feeder_mode:` line, so `out_len`/`in_len` are only set via the
+        // `feeder_cycle:` line shape itself.
+        let feeder = vec!["feeder_cycle:400,out_len:13,in_len:14".to_string()];
+        let first = match parse("feeder_pro", &feeder) {
+            ParsedMessage::ProcessFeeder(p) => p,
+            other => panic!("unexpected parse result: {:?}", other),
+        };
+        let second = match parse("feeder_pro", &first.to_protocol_lines()) {
+            ParsedMessage::ProcessFeeder(p) => p,
+            other => panic!("unexpected re-parse result: {:?}", other),
+        };
+        assert_eq!(first.feeder_out_len, second.feeder_out_len);
+        assert_eq!(first.feeder_in_len, second.feeder_in_len);
+        assert_eq!(second.feeder_out_len, Some(13));
+        assert_eq!(second.feeder_in_len, Some(14));
+    }
+
+    #[test]
+    fn test_other_variants_round_trip_via_parsed_message_to_protocol_lines() {
+        let free = parse(
+            "free",
+            &[
+                "total   : 64424".to_string(),
+                "used    : 60776".to_string(),
+                "maximum : 60916".to_string(),
+            ],
+        );
+        match parse("free", &free.to_protocol_lines()) {
+            ParsedMessage::Free(f) => {
+                assert_eq!(f.total, 64424);
+                assert_eq!(f.used, 60776);
+                assert_eq!(f.maximum, 60916);
+            }
+            other => panic!("unexpected re-parse result: {:?}", other),
+        }
+
+        let ifconfig = parse(
+            "ifconfig",
+            &[
+                "network interface device: en0".to_string(),
+                "MTU: 1500".to_string(),
+                "ip address: 10.0.0.5".to_string(),
+            ],
+        );
+        match parse("ifconfig", &ifconfig.to_protocol_lines()) {
+            ParsedMessage::IfConfig(c) => {
+                assert_eq!(c.iface.as_deref(), Some("en0"));
+                assert_eq!(c.mtu, Some(1500));
+                assert_eq!(c.ip_addr.as_deref(), Some("10.0.0.5"));
+            }
+            other => panic!("unexpected re-parse result: {:?}", other),
+        }
+
+        let worktime = parse(
+            "worktime",
+            &[
+                "Start date: 2024-01-01 10:30:00".to_string(),
+                "Total startup time: 1:02:03".to_string(),
+                "decodeTimes: 7".to_string(),
+            ],
+        );
+        match parse("worktime", &worktime.to_protocol_lines()) {
+            ParsedMessage::WorkTime(wt) => {
+                assert_eq!(
+                    wt.start_date.and_then(|d| d.as_parsed()),
+                    chrono::NaiveDateTime::parse_from_str("2024-01-01 10:30:00", "%Y-%m-%d %H:%M:%S").ok()
+                );
+                assert_eq!(
+                    wt.total_startup_time.and_then(|d| d.as_parsed()),
+                    Some(chrono::Duration::seconds(3723))
+                );
+                assert_eq!(wt.decode_times, Some(7));
+            }
+            other => panic!("unexpected re-parse result: {:?}", other),
+        }
+
+        let error = parse("error", &["ERROR(0x0000007) INTERLOCK GND_LOCK".to_string()]);
+        match parse("error", &error.to_protocol_lines()) {
+            ParsedMessage::Error(fw) => {
+                assert_eq!(fw.raw, "0x0000007");
+                assert_eq!(fw.highest_severity, Some(Severity::Error));
+            }
+            other => panic!("unexpected re-parse result: {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,253 @@
+//! Version-aware parser dispatch.
+//!
+//! `parse_process_like` hard-codes each firmware's line shapes (`power:`,
+//! `head mode:`, `pulse tick`, ...). That's fine for the firmware revisions
+//! we've seen, but a renamed key on a new build would otherwise need a code
+//! change. `ParserRegistry` holds a `(firmware_version, command) -> field
+//! rules` table loaded from TOML (`SchemaTable::from_toml_str`; no other
+//! format is read) and consults it before falling back to the built-in
+//! parsers, so supporting a new revision is a config edit.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::parser::{self, split_kv, KVLine, ParsedMessage, ProcessParams};
+
+/// How to split one reply line into key/value pairs, and how to map the
+/// resulting keys onto `ProcessParams` fields.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldRule {
+    /// Only apply this rule to lines starting with this prefix (after
+    /// trimming). Empty matches every line.
+    #[serde(default)]
+    pub line_prefix: String,
+    /// Character that separates `key` from `value` within a segment.
+    #[serde(default = "default_split_char")]
+    pub split_char: char,
+    /// Character that separates segments on one line.
+    #[serde(default = "default_segment_sep")]
+    pub segment_sep: char,
+    /// Device key -> `ProcessParams` field name.
+    pub fields: HashMap<String, String>,
+}
+
+fn default_split_char() -> char {
+    ':'
+}
+
+fn default_segment_sep() -> char {
+    ','
+}
+
+/// Field rules for one `(firmware_version, command)` pair.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandSchema {
+    pub version: String,
+    pub command: String,
+    pub rules: Vec<FieldRule>,
+}
+
+/// Commands `apply_schema` knows how to turn field rules into a
+/// `ProcessParams`-shaped `ParsedMessage`. A schema entry for any other
+/// command has nowhere to go in the current output model, so it's dropped
+/// at load time rather than silently misrendered as a process reply.
+const SUPPORTED_COMMANDS: &[&str] = &["cur_pro", "feeder_pro"];
+
+/// The full schema table, as loaded from TOML: a flat list of
+/// per-version, per-command rule sets.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct SchemaTable {
+    #[serde(default, rename = "schema")]
+    pub entries: Vec<CommandSchema>,
+}
+
+impl SchemaTable {
+    pub fn from_toml_str(s: &str) -> Result<Self> {
+        let mut table: SchemaTable = toml::from_str(s)?;
+        table
+            .entries
+            .retain(|e| SUPPORTED_COMMANDS.contains(&e.command.as_str()));
+        Ok(table)
+    }
+
+    fn lookup(&self, version: &str, command: &str) -> Option<&CommandSchema> {
+        self.entries
+            .iter()
+            .find(|e| e.version == version && e.command == command)
+    }
+}
+
+/// Holds the parsed schema plus the firmware version currently detected on
+/// the wire. `ParsedMessage` stays the stable typed output regardless of
+/// which rule set (or the built-in fallback) produced it.
+pub struct ParserRegistry {
+    schema: SchemaTable,
+    active_version: String,
+}
+
+impl ParserRegistry {
+    pub fn new(schema: SchemaTable, active_version: impl Into<String>) -> Self {
+        Self {
+            schema,
+            active_version: active_version.into(),
+        }
+    }
+
+    pub fn set_active_version(&mut self, version: impl Into<String>) {
+        self.active_version = version.into();
+    }
+
+    /// Parse `lines` as a reply to `cmd`, consulting the schema for the
+    /// active firmware version first and falling back to the built-in
+    /// parser when no entry matches. Only `cur_pro`/`feeder_pro` can have a
+    /// schema entry at all (see [`SUPPORTED_COMMANDS`]); every other
+    /// command always goes through the built-in parser.
+    pub fn parse(&self, cmd: &str, lines: &[String]) -> ParsedMessage {
+        if SUPPORTED_COMMANDS.contains(&cmd) {
+            if let Some(schema) = self.schema.lookup(&self.active_version, cmd) {
+                let params = apply_schema(schema, lines);
+                return match cmd {
+                    "feeder_pro" => ParsedMessage::ProcessFeeder(params),
+                    _ => ParsedMessage::ProcessCur(params),
+                };
+            }
+        }
+        parser::parse(cmd, lines)
+    }
+}
+
+/// Apply a command's field rules to its reply lines, producing the same
+/// `ProcessParams` shape the built-in `parse_process_like` does.
+fn apply_schema(schema: &CommandSchema, lines: &[String]) -> ProcessParams {
+    let mut p = ProcessParams::default();
+    for raw in lines {
+        let line = raw.trim();
+        let Some(rule) = schema
+            .rules
+            .iter()
+            .find(|r| r.line_prefix.is_empty() || line.starts_with(r.line_prefix.as_str()))
+        else {
+            if let Some(kv) = split_kv(line) {
+                p.extras.push(kv);
+            }
+            continue;
+        };
+
+        for segment in line.split(rule.segment_sep) {
+            let Some((key, value)) = segment.split_once(rule.split_char) else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+            match rule.fields.get(key) {
+                Some(field) => set_field(&mut p, field, value),
+                None => p.extras.push(KVLine {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                }),
+            }
+        }
+    }
+    p
+}
+
+fn set_field(p: &mut ProcessParams, field: &str, value: &str) {
+    let Ok(n) = value.parse::<i64>() else {
+        return;
+    };
+    match field {
+        "power" => p.power = Some(n),
+        "pwm_fre" => p.pwm_fre = Some(n),
+        "pwm_duty" => p.pwm_duty = Some(n),
+        "mode" => p.mode = Some(n),
+        "head_mode" => p.head_mode = Some(n),
+        "head_fre" => p.head_fre = Some(n),
+        "head_width" => p.head_width = Some(n),
+        "pulse_on" => p.pulse_on = Some(n),
+        "pulse_off" => p.pulse_off = Some(n),
+        "gas_early" => p.gas_early = Some(n),
+        "gas_delay" => p.gas_delay = Some(n),
+        "pow_rise" => p.pow_rise = Some(n),
+        "pow_fall" => p.pow_fall = Some(n),
+        "pow_early" => p.pow_early = Some(n),
+        "pow_delay" => p.pow_delay = Some(n),
+        "power_on" => p.power_on = Some(n),
+        "power_off" => p.power_off = Some(n),
+        "index" => p.index = Some(n),
+        "feeder_mode" => p.feeder_mode = Some(n),
+        "feeder_out_speed" => p.feeder_out_speed = Some(n),
+        "feeder_out_len" => p.feeder_out_len = Some(n),
+        "feeder_in_speed" => p.feeder_in_speed = Some(n),
+        "feeder_in_len" => p.feeder_in_len = Some(n),
+        "feeder_cycle" => p.feeder_cycle = Some(n),
+        "feeder_smoothness" => p.feeder_smoothness = Some(n),
+        "feeder_out_delay" => p.feeder_out_delay = Some(n),
+        "feeder_in_delay" => p.feeder_in_delay = Some(n),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[[schema]]
+version = "9.9.9"
+command = "cur_pro"
+
+[[schema.rules]]
+line_prefix = "pwr:"
+fields = { pwr = "power" }
+
+[[schema]]
+version = "9.9.9"
+command = "status"
+
+[[schema.rules]]
+line_prefix = ""
+fields = { foo = "power" }
+"#;
+
+    #[test]
+    fn test_unsupported_command_entries_are_dropped_at_load() {
+        let table = SchemaTable::from_toml_str(TOML).unwrap();
+        assert_eq!(table.entries.len(), 1);
+        assert_eq!(table.entries[0].command, "cur_pro");
+    }
+
+    #[test]
+    fn test_schema_hit_is_used_for_matching_version_and_command() {
+        let table = SchemaTable::from_toml_str(TOML).unwrap();
+        let registry = ParserRegistry::new(table, "9.9.9");
+        let lines = vec!["pwr:42".to_string()];
+        match registry.parse("cur_pro", &lines) {
+            ParsedMessage::ProcessCur(p) => assert_eq!(p.power, Some(42)),
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_falls_back_to_builtin_parser_on_version_miss() {
+        let table = SchemaTable::from_toml_str(TOML).unwrap();
+        let registry = ParserRegistry::new(table, "1.0.0");
+        let lines = vec!["power:100,fre:3000,duty:100,mode:0".to_string()];
+        match registry.parse("cur_pro", &lines) {
+            ParsedMessage::ProcessCur(p) => assert_eq!(p.power, Some(100)),
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_falls_back_to_builtin_parser_for_unscheduled_command() {
+        let table = SchemaTable::from_toml_str(TOML).unwrap();
+        let registry = ParserRegistry::new(table, "9.9.9");
+        let lines = vec!["total   : 64424".to_string()];
+        match registry.parse("free", &lines) {
+            ParsedMessage::Free(_) => {}
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+}
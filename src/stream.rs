@@ -0,0 +1,177 @@
+//! Incremental, I/O-agnostic framing on top of [`parse`]: feed raw bytes as
+//! they arrive off the UART and get back completed [`ParsedMessage`] values
+//! as each reply's frame (ending at the `msh >` prompt) closes.
+
+use crate::parser::{self, normalize_line, ParsedMessage, PROMPT};
+
+/// Default cap on the unterminated-line buffer. Long past any real `msh`
+/// reply line; exists so a wedged device or a dropped framing byte can't
+/// grow `partial` without bound for the life of a long-running monitor.
+const DEFAULT_MAX_PARTIAL_LEN: usize = 4096;
+
+/// Accumulates bytes pushed from an external poll loop and emits
+/// [`ParsedMessage`] values as replies complete.
+///
+/// `StreamParser` does no I/O itself; callers drive it from their own
+/// `AsRawFd`/poll loop by calling [`push_bytes`](Self::push_bytes) whenever
+/// the fd is readable.
+pub struct StreamParser {
+    /// Bytes belonging to a line that hasn't seen `\n` yet.
+    partial: Vec<u8>,
+    /// Lines collected for the reply currently being framed.
+    pending_lines: Vec<String>,
+    /// Command the pending lines are a reply to, set by [`set_current_command`](Self::set_current_command).
+    current_cmd: Option<String>,
+    /// Cap on `partial`'s length; see [`DEFAULT_MAX_PARTIAL_LEN`].
+    max_partial_len: usize,
+}
+
+impl StreamParser {
+    pub fn new() -> Self {
+        Self {
+            partial: Vec::new(),
+            pending_lines: Vec::new(),
+            current_cmd: None,
+            max_partial_len: DEFAULT_MAX_PARTIAL_LEN,
+        }
+    }
+
+    /// Override the cap on the unterminated-line buffer (default
+    /// [`DEFAULT_MAX_PARTIAL_LEN`]).
+    pub fn with_max_partial_len(mut self, max_partial_len: usize) -> Self {
+        self.max_partial_len = max_partial_len;
+        self
+    }
+
+    /// Record which command the next completed frame is a reply to. Call
+    /// this right after writing the command to the device.
+    pub fn set_current_command(&mut self, cmd: &str) {
+        self.current_cmd = Some(cmd.to_string());
+        self.pending_lines.clear();
+    }
+
+    /// Feed newly-read bytes. Splits on `\r?\n`, accumulates lines until the
+    /// `msh >` prompt reappears (the frame boundary), then parses the
+    /// accumulated lines as a reply to the current command and returns any
+    /// messages that completed. Any partial trailing line is retained for
+    /// the next call.
+    ///
+    /// If a run with no `\n` grows past `max_partial_len` (a wedged device,
+    /// dropped framing byte, or other noise the protocol doesn't produce in
+    /// practice), the oldest bytes are dropped so the buffer stays bounded;
+    /// the line it belonged to is lost, but a later well-formed frame can
+    /// still be recovered.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Vec<ParsedMessage> {
+        self.partial.extend_from_slice(bytes);
+
+        let mut completed = Vec::new();
+        while let Some(nl_pos) = self.partial.iter().position(|&b| b == b'\n') {
+            let raw_line = self.partial.drain(..=nl_pos).collect::<Vec<u8>>();
+            let line = String::from_utf8_lossy(&raw_line).into_owned();
+            let line = normalize_line(&line);
+            if line.is_empty() {
+                continue;
+            }
+
+            if line == PROMPT {
+                if let Some(cmd) = self.current_cmd.take() {
+                    let lines = std::mem::take(&mut self.pending_lines);
+                    completed.push(parser::parse(&cmd, &lines));
+                } else {
+                    self.pending_lines.clear();
+                }
+                continue;
+            }
+
+            self.pending_lines.push(line);
+        }
+
+        // Only the unterminated remainder (no `\n` left in it) can still be
+        // noise; cap it now so a complete line extracted above is never lost.
+        if self.partial.len() > self.max_partial_len {
+            let excess = self.partial.len() - self.max_partial_len;
+            self.partial.drain(..excess);
+        }
+
+        completed
+    }
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_boundary_split_across_push_bytes_calls() {
+        let mut sp = StreamParser::new();
+        sp.set_current_command("free");
+
+        // Split the reply mid-line and mid-prompt across several calls.
+        assert!(sp.push_bytes(b"total   : 64424\r\nused").is_empty());
+        assert!(sp.push_bytes(b"    : 60776\r\nmaximum : 60916\r\n").is_empty());
+        assert!(sp.push_bytes(b"msh").is_empty());
+        let completed = sp.push_bytes(b" >\r\n");
+        assert_eq!(completed.len(), 1);
+        match &completed[0] {
+            ParsedMessage::Free(f) => {
+                assert_eq!(f.total, 64424);
+                assert_eq!(f.used, 60776);
+                assert_eq!(f.maximum, 60916);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_two_replies_in_consecutive_pushes_both_complete() {
+        let mut sp = StreamParser::new();
+        sp.set_current_command("mode");
+        let completed = sp.push_bytes(b"running\r\nmsh >\r\n");
+        assert_eq!(completed.len(), 1);
+
+        sp.set_current_command("state");
+        let completed = sp.push_bytes(b"idle\r\nmsh >\r\n");
+        assert_eq!(completed.len(), 1);
+        match (&completed[0],) {
+            (ParsedMessage::State { state },) => assert_eq!(state, "idle"),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_partial_buffer_is_capped() {
+        let mut sp = StreamParser::new().with_max_partial_len(16);
+        // No newline anywhere: an unbounded run of noise must not grow
+        // `partial` past the cap.
+        sp.push_bytes(&[b'x'; 1000]);
+        assert!(sp.partial.len() <= 16);
+    }
+
+    #[test]
+    fn test_cap_does_not_drop_a_completed_line_in_the_same_push() {
+        let mut sp = StreamParser::new().with_max_partial_len(10);
+        sp.set_current_command("unrecognized_cmd");
+        // Stale unterminated leftover from a previous call.
+        sp.push_bytes(b"AAAA");
+        // This single call pushes the combined buffer ("AAAAB\nCCCCCCCCCC\n")
+        // over the cap, but it also terminates the first line with `\n`
+        // before the excess noise; that line must survive extraction whole,
+        // and only the still-unterminated remainder is subject to the cap.
+        sp.push_bytes(b"B\nCCCCCCCCCC\n");
+        let completed = sp.push_bytes(b"msh >\r\n");
+        assert_eq!(completed.len(), 1);
+        match &completed[0] {
+            ParsedMessage::Unknown { lines, .. } => {
+                assert!(lines.contains(&"AAAAB".to_string()));
+                assert!(lines.contains(&"CCCCCCCCCC".to_string()));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+}
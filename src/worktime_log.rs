@@ -0,0 +1,136 @@
+//! Aggregation over a sequence of decoded [`WorkTime`] snapshots, turning
+//! the otherwise-opaque `worktime` command into something useful for usage
+//! tracking and licensing.
+
+use chrono::{Duration, NaiveDateTime};
+
+use crate::parser::WorkTime;
+
+/// Collects `WorkTime` snapshots (e.g. one per poll of the `worktime`
+/// command) and answers aggregate queries over them.
+#[derive(Debug, Default)]
+pub struct WorkTimeLog {
+    snapshots: Vec<WorkTime>,
+}
+
+impl WorkTimeLog {
+    pub fn new() -> Self {
+        Self {
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Record another snapshot, most recent last.
+    pub fn push(&mut self, snapshot: WorkTime) {
+        self.snapshots.push(snapshot);
+    }
+
+    /// Total accumulated runtime, taken from the latest snapshot's
+    /// `"Total startup time"` field.
+    pub fn total_runtime(&self) -> Option<Duration> {
+        self.snapshots
+            .last()
+            .and_then(|wt| wt.total_startup_time.as_ref())
+            .and_then(|d| d.as_parsed())
+    }
+
+    /// Number of decode events (`decodeTimes` deltas) whose snapshot's
+    /// `"Current date"` falls within `[start, end]`.
+    pub fn decode_events_in_window(&self, start: NaiveDateTime, end: NaiveDateTime) -> i64 {
+        let mut prev_count: Option<i64> = None;
+        let mut total = 0i64;
+        for wt in &self.snapshots {
+            let in_window = wt
+                .current_date
+                .as_ref()
+                .and_then(|d| d.as_parsed())
+                .map(|dt| dt >= start && dt <= end)
+                .unwrap_or(false);
+            if let (Some(prev), Some(cur)) = (prev_count, wt.decode_times) {
+                if in_window && cur > prev {
+                    total += cur - prev;
+                }
+            }
+            if let Some(cur) = wt.decode_times {
+                prev_count = Some(cur);
+            }
+        }
+        total
+    }
+
+    /// `(oldest, newest)` "Days available" readings, to eyeball the trend
+    /// (e.g. whether a licensed time budget is depleting on schedule).
+    pub fn days_available_trend(&self) -> Option<(i64, i64)> {
+        let oldest = self.snapshots.iter().find_map(|wt| wt.days_available)?;
+        let newest = self
+            .snapshots
+            .iter()
+            .rev()
+            .find_map(|wt| wt.days_available)?;
+        Some((oldest, newest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{WorkTimeDate, WorkTimeDuration};
+
+    fn snapshot(
+        current_date: &str,
+        total_startup_secs: i64,
+        decode_times: i64,
+        days_available: i64,
+    ) -> WorkTime {
+        WorkTime {
+            start_date: None,
+            end_date: None,
+            current_date: Some(WorkTimeDate::Parsed(
+                NaiveDateTime::parse_from_str(current_date, "%Y-%m-%d %H:%M:%S").unwrap(),
+            )),
+            total_startup_time: Some(WorkTimeDuration::Parsed(Duration::seconds(
+                total_startup_secs,
+            ))),
+            current_startup_time: None,
+            onkey_time: None,
+            decode_times: Some(decode_times),
+            days_available: Some(days_available),
+        }
+    }
+
+    #[test]
+    fn test_total_runtime_is_latest_snapshot() {
+        let mut log = WorkTimeLog::new();
+        log.push(snapshot("2024-01-01 00:00:00", 100, 1, 30));
+        log.push(snapshot("2024-01-02 00:00:00", 200, 2, 29));
+        assert_eq!(log.total_runtime(), Some(Duration::seconds(200)));
+    }
+
+    #[test]
+    fn test_decode_events_in_window_counts_only_in_range_deltas() {
+        let mut log = WorkTimeLog::new();
+        log.push(snapshot("2024-01-01 00:00:00", 100, 10, 30));
+        log.push(snapshot("2024-01-02 00:00:00", 200, 15, 29)); // +5, in window
+        log.push(snapshot("2024-06-01 00:00:00", 300, 25, 28)); // +10, outside window
+
+        let start = NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        let end = NaiveDateTime::parse_from_str("2024-02-01 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(log.decode_events_in_window(start, end), 5);
+    }
+
+    #[test]
+    fn test_days_available_trend_oldest_to_newest() {
+        let mut log = WorkTimeLog::new();
+        log.push(snapshot("2024-01-01 00:00:00", 100, 1, 30));
+        log.push(snapshot("2024-01-02 00:00:00", 200, 2, 29));
+        log.push(snapshot("2024-01-03 00:00:00", 300, 3, 27));
+        assert_eq!(log.days_available_trend(), Some((30, 27)));
+    }
+
+    #[test]
+    fn test_empty_log_returns_none() {
+        let log = WorkTimeLog::new();
+        assert_eq!(log.total_runtime(), None);
+        assert_eq!(log.days_available_trend(), None);
+    }
+}